@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use error::IntelError;
+use rustorm::EntityManager;
+use rustorm::Table;
+use rustorm::TableName;
+use search::SearchHit;
+use search::SearchIndex;
+use tab::Tab;
+use window;
+use window::Window;
+use window_config;
+use window_config::WindowConfig;
+
+/// number of distinct `db_url`s to keep derived data for before evicting
+/// the least-recently-used entry
+const DEFAULT_CAPACITY: usize = 20;
+
+/// how long a cached entry stays valid before it is transparently re-derived
+const DEFAULT_TTL_SECS: u64 = 5 * 60;
+
+lazy_static! {
+    pub static ref CACHE_POOL: Mutex<CachePool> = Mutex::new(CachePool::new());
+}
+
+struct CacheEntry<T> {
+    value: T,
+    inserted: Instant,
+}
+
+impl<T> CacheEntry<T> {
+    fn new(value: T) -> Self {
+        CacheEntry {
+            value,
+            inserted: Instant::now(),
+        }
+    }
+
+    fn is_expired(&self, ttl: Duration) -> bool {
+        self.inserted.elapsed() > ttl
+    }
+}
+
+/// a capacity-bounded, per-`db_url` cache of derived `Table`s and `Window`s
+///
+/// entries are evicted least-recently-used first once `capacity` is
+/// exceeded, and each entry additionally expires after `ttl`, so a
+/// connected schema change is eventually picked up without restarting
+/// the process. `invalidate`/`invalidate_all` force this re-derivation
+/// immediately, e.g. after a DDL operation or an admin action.
+pub struct CachePool {
+    capacity: usize,
+    ttl: Duration,
+    tables: HashMap<String, CacheEntry<Vec<Table>>>,
+    windows: HashMap<String, CacheEntry<Vec<Window>>>,
+    /// the search index over `windows`, rebuilt alongside it
+    indexes: HashMap<String, CacheEntry<SearchIndex>>,
+    /// lazily loaded has_many/indirect tabs, keyed by `db_url` then table
+    tabs: HashMap<String, HashMap<String, CacheEntry<Tab>>>,
+    /// least-recently-used first, most-recently-used last
+    recency: Vec<String>,
+}
+
+impl CachePool {
+    fn new() -> Self {
+        CachePool::with_capacity_and_ttl(DEFAULT_CAPACITY, Duration::from_secs(DEFAULT_TTL_SECS))
+    }
+
+    pub fn with_capacity_and_ttl(capacity: usize, ttl: Duration) -> Self {
+        CachePool {
+            capacity,
+            ttl,
+            tables: HashMap::new(),
+            windows: HashMap::new(),
+            indexes: HashMap::new(),
+            tabs: HashMap::new(),
+            recency: Vec::new(),
+        }
+    }
+
+    pub fn get_cached_tables(
+        &mut self,
+        em: &EntityManager,
+        db_url: &str,
+    ) -> Result<Vec<Table>, IntelError> {
+        if let Some(tables) = self.fresh_tables(db_url) {
+            self.touch(db_url);
+            return Ok(tables);
+        }
+        let tables = em.get_all_tables()?;
+        self.tables
+            .insert(db_url.to_string(), CacheEntry::new(tables.clone()));
+        self.touch(db_url);
+        self.evict_if_needed();
+        Ok(tables)
+    }
+
+    pub fn get_cached_windows(
+        &mut self,
+        em: &EntityManager,
+        db_url: &str,
+    ) -> Result<Vec<Window>, IntelError> {
+        if let Some(windows) = self.fresh_windows(db_url) {
+            self.touch(db_url);
+            return Ok(windows);
+        }
+        let tables = self.get_cached_tables(em, db_url)?;
+        let config = WindowConfig::load(&window_config::path_for_db(db_url))?;
+        let windows = window::derive_all_windows(em, &tables, &config);
+        let index = SearchIndex::build(&windows);
+        self.windows
+            .insert(db_url.to_string(), CacheEntry::new(windows.clone()));
+        self.indexes
+            .insert(db_url.to_string(), CacheEntry::new(index));
+        self.touch(db_url);
+        self.evict_if_needed();
+        Ok(windows)
+    }
+
+    /// search the windows cached for `db_url`, deriving them first if needed
+    pub fn search(
+        &mut self,
+        em: &EntityManager,
+        db_url: &str,
+        query: &str,
+    ) -> Result<Vec<SearchHit>, IntelError> {
+        self.get_cached_windows(em, db_url)?;
+        let index = self
+            .indexes
+            .get(db_url)
+            .expect("just (re)built alongside the windows above");
+        Ok(index.value.search(query))
+    }
+
+    /// build (or return the already-cached) full `Tab` for a has_many or
+    /// indirect relation of `window` that was derived as a lightweight
+    /// `TabDescriptor`
+    pub fn get_cached_tab(
+        &mut self,
+        db_url: &str,
+        window: &Window,
+        table_name: &TableName,
+        all_tables: &Vec<Table>,
+    ) -> Result<Tab, IntelError> {
+        let key = tab_key(table_name);
+        if let Some(tab) = self.tabs.get(db_url).and_then(|tabs| tabs.get(&key)).and_then(
+            |entry| {
+                if entry.is_expired(self.ttl) {
+                    None
+                } else {
+                    Some(entry.value.clone())
+                }
+            },
+        ) {
+            self.touch(db_url);
+            return Ok(tab);
+        }
+        let tab = window::load_tab(window, table_name, all_tables)
+            .ok_or_else(|| IntelError::TableNotFound(table_name.to_owned()))?;
+        self.tabs
+            .entry(db_url.to_string())
+            .or_insert_with(HashMap::new)
+            .insert(key, CacheEntry::new(tab.clone()));
+        self.touch(db_url);
+        self.evict_if_needed();
+        Ok(tab)
+    }
+
+    fn fresh_tables(&self, db_url: &str) -> Option<Vec<Table>> {
+        self.tables.get(db_url).and_then(|entry| {
+            if entry.is_expired(self.ttl) {
+                None
+            } else {
+                Some(entry.value.clone())
+            }
+        })
+    }
+
+    fn fresh_windows(&self, db_url: &str) -> Option<Vec<Window>> {
+        self.windows.get(db_url).and_then(|entry| {
+            if entry.is_expired(self.ttl) {
+                None
+            } else {
+                Some(entry.value.clone())
+            }
+        })
+    }
+
+    fn touch(&mut self, db_url: &str) {
+        self.recency.retain(|k| k != db_url);
+        self.recency.push(db_url.to_string());
+    }
+
+    fn evict_if_needed(&mut self) {
+        while self.recency.len() > self.capacity {
+            let lru = self.recency.remove(0);
+            self.tables.remove(&lru);
+            self.windows.remove(&lru);
+            self.indexes.remove(&lru);
+            self.tabs.remove(&lru);
+        }
+    }
+
+    /// force re-derivation of `db_url` the next time it is looked up
+    pub fn invalidate(&mut self, db_url: &str) {
+        self.tables.remove(db_url);
+        self.windows.remove(db_url);
+        self.indexes.remove(db_url);
+        self.tabs.remove(db_url);
+        self.recency.retain(|k| k != db_url);
+    }
+
+    /// force re-derivation of every cached `db_url`
+    pub fn invalidate_all(&mut self) {
+        self.tables.clear();
+        self.windows.clear();
+        self.indexes.clear();
+        self.tabs.clear();
+        self.recency.clear();
+    }
+}
+
+/// cache key for a table within a `db_url`'s lazily-loaded tabs
+fn tab_key(table_name: &TableName) -> String {
+    format!(
+        "{}.{}",
+        table_name.schema.to_owned().unwrap_or_default(),
+        table_name.name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_past_capacity() {
+        let mut pool = CachePool::with_capacity_and_ttl(2, Duration::from_secs(60));
+        pool.tables
+            .insert("a".to_string(), CacheEntry::new(Vec::new()));
+        pool.touch("a");
+        pool.tables
+            .insert("b".to_string(), CacheEntry::new(Vec::new()));
+        pool.touch("b");
+        pool.tables
+            .insert("c".to_string(), CacheEntry::new(Vec::new()));
+        pool.touch("c");
+        pool.evict_if_needed();
+
+        assert!(!pool.tables.contains_key("a"));
+        assert!(pool.tables.contains_key("b"));
+        assert!(pool.tables.contains_key("c"));
+    }
+
+    #[test]
+    fn invalidate_removes_a_single_entry() {
+        let mut pool = CachePool::with_capacity_and_ttl(10, Duration::from_secs(60));
+        pool.tables
+            .insert("a".to_string(), CacheEntry::new(Vec::new()));
+        pool.touch("a");
+        pool.tables
+            .insert("b".to_string(), CacheEntry::new(Vec::new()));
+        pool.touch("b");
+
+        pool.invalidate("a");
+
+        assert!(!pool.tables.contains_key("a"));
+        assert!(pool.tables.contains_key("b"));
+    }
+
+    #[test]
+    fn expired_entry_is_not_considered_fresh() {
+        let mut pool = CachePool::with_capacity_and_ttl(10, Duration::from_secs(0));
+        pool.tables
+            .insert("a".to_string(), CacheEntry::new(Vec::new()));
+
+        assert!(pool.fresh_tables("a").is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+
+use rustorm::DbError;
+use rustorm::TableName;
+
+/// the `intel` crate's error type: wraps the lower-level errors it can
+/// hit while deriving/caching windows, plus the failure modes specific
+/// to that derivation
+#[derive(Debug)]
+pub enum IntelError {
+    Db(DbError),
+    Io(io::Error),
+    Serde(::serde_json::Error),
+    /// a `TabDescriptor`/relation named a table that isn't in the
+    /// connected database's `Vec<Table>` anymore, e.g. a has_many or
+    /// indirect tab opened against a schema that changed since the
+    /// windows it was derived from were cached
+    TableNotFound(TableName),
+}
+
+impl fmt::Display for IntelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IntelError::Db(ref e) => write!(f, "{}", e),
+            IntelError::Io(ref e) => write!(f, "{}", e),
+            IntelError::Serde(ref e) => write!(f, "{}", e),
+            IntelError::TableNotFound(ref table_name) => {
+                write!(f, "table not found: {}", table_name.name)
+            }
+        }
+    }
+}
+
+impl StdError for IntelError {
+    fn description(&self) -> &str {
+        match *self {
+            IntelError::Db(_) => "database error",
+            IntelError::Io(_) => "io error",
+            IntelError::Serde(_) => "serialization error",
+            IntelError::TableNotFound(_) => "table not found",
+        }
+    }
+}
+
+impl From<DbError> for IntelError {
+    fn from(e: DbError) -> Self {
+        IntelError::Db(e)
+    }
+}
+
+impl From<io::Error> for IntelError {
+    fn from(e: io::Error) -> Self {
+        IntelError::Io(e)
+    }
+}
+
+impl From<::serde_json::Error> for IntelError {
+    fn from(e: ::serde_json::Error) -> Self {
+        IntelError::Serde(e)
+    }
+}
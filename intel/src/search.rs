@@ -0,0 +1,300 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use rustorm::TableName;
+use tab::Tab;
+use window::TabDescriptor;
+use window::Window;
+use window::WindowName;
+
+/// where a query token was found within a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum MatchField {
+    Name,
+    Description,
+    Column,
+}
+
+impl MatchField {
+    /// name match ranks above description, which ranks above a column match
+    fn score(&self) -> u32 {
+        match *self {
+            MatchField::Name => 3,
+            MatchField::Description => 2,
+            MatchField::Column => 1,
+        }
+    }
+}
+
+/// a window that matched a search query, annotated with which fields
+/// of it matched, ranked highest score first
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub window: WindowName,
+    pub matched_fields: Vec<MatchField>,
+    pub score: u32,
+}
+
+/// an in-memory inverted index over the derived windows, tokenizing
+/// `Window.name`, `description`, and each `Tab`'s table name and column
+/// names (main_tab, has_one, one_one, has_many, and the indirect tabs)
+///
+/// rebuilt whenever `derive_all_windows` runs so that `search` stays
+/// O(query tokens) rather than O(all columns)
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    /// token -> set of (qualified window key, field it was found in)
+    postings: HashMap<String, HashSet<(String, MatchField)>>,
+    /// qualified window key -> its display metadata, for resolving hits
+    /// back to a `WindowName`
+    windows: HashMap<String, WindowName>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        SearchIndex {
+            postings: HashMap::new(),
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn build(windows: &Vec<Window>) -> Self {
+        let mut index = SearchIndex::new();
+        for window in windows {
+            index.index_window(window);
+        }
+        index
+    }
+
+    fn index_window(&mut self, window: &Window) {
+        let key = window_key(&window.main_tab.table_name);
+        self.windows.insert(
+            key.clone(),
+            WindowName {
+                name: window.name.to_owned(),
+                table_name: window.main_tab.table_name.to_owned(),
+                is_view: window.is_view,
+            },
+        );
+        self.index_field(&window.name, &key, MatchField::Name);
+        if let Some(ref description) = window.description {
+            self.index_field(description, &key, MatchField::Description);
+        }
+        self.index_tab(&window.main_tab, &key);
+        for tab in window.one_one_tabs.iter().chain(window.has_one_tabs.iter()) {
+            self.index_tab(tab, &key);
+        }
+        for tab in window
+            .has_many_tabs
+            .iter()
+            .chain(window.indirect_tabs.iter())
+        {
+            self.index_descriptor(tab, &key);
+        }
+    }
+
+    fn index_tab(&mut self, tab: &Tab, window_key: &str) {
+        self.index_field(&tab.table_name.name, window_key, MatchField::Column);
+        for column in tab.columns.iter() {
+            self.index_field(&column.name, window_key, MatchField::Column);
+        }
+    }
+
+    fn index_descriptor(&mut self, tab: &TabDescriptor, window_key: &str) {
+        self.index_field(&tab.table_name.name, window_key, MatchField::Column);
+        for column in tab.columns.iter() {
+            self.index_field(&column.name, window_key, MatchField::Column);
+        }
+    }
+
+    fn index_field(&mut self, text: &str, window_key: &str, field: MatchField) {
+        for token in tokenize(text) {
+            self.postings
+                .entry(token)
+                .or_insert_with(HashSet::new)
+                .insert((window_key.to_string(), field));
+        }
+    }
+
+    /// tokenize the query the same way the index was built, then require
+    /// every token to match a window (true intersection of the per-token
+    /// posting lists) before ranking: a name match outranks a description
+    /// match, which outranks a column match. a multi-word query like
+    /// "user settings" only returns windows matching both words, not
+    /// either one
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let tokens = tokenize(query);
+        if tokens.is_empty() {
+            return Vec::new();
+        }
+        let mut matched_fields: HashMap<String, HashSet<MatchField>> = HashMap::new();
+        let mut matched_windows: Option<HashSet<String>> = None;
+        for token in &tokens {
+            let mut windows_for_token: HashSet<String> = HashSet::new();
+            if let Some(postings) = self.postings.get(token) {
+                for &(ref window_key, field) in postings {
+                    windows_for_token.insert(window_key.clone());
+                    matched_fields
+                        .entry(window_key.clone())
+                        .or_insert_with(HashSet::new)
+                        .insert(field);
+                }
+            }
+            matched_windows = Some(match matched_windows {
+                Some(ref windows) => windows.intersection(&windows_for_token).cloned().collect(),
+                None => windows_for_token,
+            });
+        }
+        let matched_windows = matched_windows.unwrap_or_else(HashSet::new);
+        let mut hits: Vec<SearchHit> = matched_fields
+            .into_iter()
+            .filter(|&(ref window_key, _)| matched_windows.contains(window_key))
+            .filter_map(|(window_key, fields)| {
+                let window = self.windows.get(&window_key)?.to_owned();
+                let mut matched_fields: Vec<MatchField> = fields.into_iter().collect();
+                matched_fields.sort_by_key(|f| Reverse(f.score()));
+                let score = matched_fields.iter().map(|f| f.score()).sum();
+                Some(SearchHit {
+                    window,
+                    matched_fields,
+                    score,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.window.name.cmp(&b.window.name))
+        });
+        hits
+    }
+}
+
+/// schema-qualified identity for a window's main table, so two windows
+/// whose main tables share a bare name in different schemas don't
+/// collide in the index
+fn window_key(table_name: &TableName) -> String {
+    format!(
+        "{}.{}",
+        table_name.schema.to_owned().unwrap_or_default(),
+        table_name.name
+    )
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustorm::ColumnName;
+    use rustorm::TableName;
+    use tab::Tab;
+
+    fn sample_tab(table_name: &str, columns: Vec<&str>) -> Tab {
+        Tab {
+            name: table_name.to_string(),
+            description: None,
+            table_name: TableName::from(table_name),
+            columns: columns
+                .into_iter()
+                .map(|c| ColumnName::from(c))
+                .collect(),
+            is_view: false,
+        }
+    }
+
+    fn sample_descriptor(table_name: &str, columns: Vec<&str>) -> TabDescriptor {
+        TabDescriptor {
+            table_name: TableName::from(table_name),
+            linker: None,
+            display_name: None,
+            columns: columns
+                .into_iter()
+                .map(|c| ColumnName::from(c))
+                .collect(),
+        }
+    }
+
+    fn sample_window() -> Window {
+        Window {
+            name: "product".to_string(),
+            description: Some("items for sale".to_string()),
+            group: Some("bazaar".to_string()),
+            main_tab: sample_tab("product", vec!["id", "name", "price"]),
+            has_one_tabs: vec![],
+            one_one_tabs: vec![],
+            has_many_tabs: vec![sample_descriptor("review", vec!["id", "rating"])],
+            indirect_tabs: vec![],
+            recursive_tab: None,
+            is_view: false,
+        }
+    }
+
+    #[test]
+    fn name_match_outranks_column_match() {
+        let index = SearchIndex::build(&vec![sample_window()]);
+        let hits = index.search("product");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].window.name, "product");
+        assert!(hits[0].matched_fields.contains(&MatchField::Name));
+        // "product" is both the window name and the main tab's table name
+        assert_eq!(
+            hits[0].matched_fields,
+            vec![MatchField::Name, MatchField::Column]
+        );
+    }
+
+    #[test]
+    fn matches_a_column_on_a_has_many_tab() {
+        let index = SearchIndex::build(&vec![sample_window()]);
+        let hits = index.search("rating");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].matched_fields, vec![MatchField::Column]);
+    }
+
+    #[test]
+    fn multi_word_query_requires_every_token_to_match() {
+        let index = SearchIndex::build(&vec![sample_window()]);
+        // "product" alone matches, "rating" alone matches, but the two
+        // combined should only return a window containing both, not
+        // the union of the two single-word result sets
+        assert_eq!(index.search("product rating").len(), 1);
+        assert!(index.search("product nonexistent").is_empty());
+    }
+
+    #[test]
+    fn no_match_returns_no_hits() {
+        let index = SearchIndex::build(&vec![sample_window()]);
+        assert!(index.search("nonexistent").is_empty());
+    }
+
+    #[test]
+    fn same_bare_name_in_different_schemas_does_not_collide() {
+        let mut bazaar_product = sample_window();
+        bazaar_product.main_tab.table_name = TableName::from("bazaar.product");
+
+        let mut other_product = sample_window();
+        other_product.main_tab.table_name = TableName::from("other.product");
+        other_product.has_many_tabs = vec![sample_descriptor("shipment", vec!["id", "tracking_code"])];
+
+        let index = SearchIndex::build(&vec![bazaar_product, other_product]);
+
+        let hits = index.search("product");
+        assert_eq!(hits.len(), 2);
+
+        let tracking_hits = index.search("tracking_code");
+        assert_eq!(tracking_hits.len(), 1);
+        assert_eq!(tracking_hits[0].window.table_name, TableName::from("other.product"));
+
+        let rating_hits = index.search("rating");
+        assert_eq!(rating_hits.len(), 1);
+        assert_eq!(rating_hits[0].window.table_name, TableName::from("bazaar.product"));
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::path::Path;
+use std::path::PathBuf;
+
+use error::IntelError;
+
+/// user-authored overrides for a single window's derived layout
+///
+/// lets users hide noisy indirect tabs, rename tabs, reorder
+/// `has_many_tabs`/`indirect_tabs`, or pin a window's group without
+/// editing the connected database. applied by `window::derive_all_windows`
+/// after the default relationships are computed, so the raw derivation
+/// from the schema stays untouched and a cache rebuild simply reapplies
+/// whatever overrides are current.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowOverride {
+    /// table names to drop from `has_many_tabs`/`indirect_tabs`
+    #[serde(default)]
+    pub hidden_tabs: Vec<String>,
+
+    /// table name -> display name shown instead of the derived one
+    #[serde(default)]
+    pub renamed_tabs: HashMap<String, String>,
+
+    /// explicit table-name ordering for `has_many_tabs`;
+    /// tabs not listed keep their derived order, after the listed ones
+    #[serde(default)]
+    pub has_many_order: Vec<String>,
+
+    /// explicit table-name ordering for `indirect_tabs`
+    #[serde(default)]
+    pub indirect_order: Vec<String>,
+
+    /// overrides the group this window is derived into
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// selects an alternative to the backend's default `GroupingStrategy`, for
+/// a connected database where that default doesn't fit, e.g. a sqlite
+/// schema that isn't table-name-prefixed, or one that should just show as
+/// a single flat list instead of being bucketed at all
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GroupingOverride {
+    /// group windows by the table name prefix up to the first underscore
+    TableNamePrefix,
+    /// put every window in a single, fixed bucket
+    SingleBucket(String),
+}
+
+/// all window overrides for one connected database, keyed by the main
+/// table name (e.g. `"product"`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowConfig {
+    #[serde(default)]
+    overrides: HashMap<String, WindowOverride>,
+
+    /// overrides the `GroupingStrategy` derived by default from the
+    /// connected backend; `None` keeps deriving it from the platform
+    #[serde(default)]
+    grouping: Option<GroupingOverride>,
+}
+
+impl WindowConfig {
+    pub fn new() -> Self {
+        WindowConfig {
+            overrides: HashMap::new(),
+            grouping: None,
+        }
+    }
+
+    pub fn get(&self, main_table: &str) -> Option<&WindowOverride> {
+        self.overrides.get(main_table)
+    }
+
+    pub fn set(&mut self, main_table: &str, window_override: WindowOverride) {
+        self.overrides.insert(main_table.to_string(), window_override);
+    }
+
+    pub fn grouping(&self) -> Option<&GroupingOverride> {
+        self.grouping.as_ref()
+    }
+
+    pub fn set_grouping(&mut self, grouping: Option<GroupingOverride>) {
+        self.grouping = grouping;
+    }
+
+    /// load the sidecar document for a `db_url`, returning an empty config
+    /// when none has been saved yet
+    pub fn load(path: &Path) -> Result<Self, IntelError> {
+        if !path.exists() {
+            return Ok(WindowConfig::new());
+        }
+        let content = fs::read_to_string(path)?;
+        let config = ::serde_json::from_str(&content)?;
+        Ok(config)
+    }
+
+    /// persist this config so the client's edits survive a restart
+    pub fn save(&self, path: &Path) -> Result<(), IntelError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = ::serde_json::to_string_pretty(self)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// the sidecar document path for a `db_url`, anchored under this app's
+/// config directory rather than a bare relative path - a server started
+/// with a working directory that isn't the project root (systemd, docker,
+/// a supervisor) still reads/writes the same file every time
+pub fn path_for_db(db_url: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    db_url.hash(&mut hasher);
+    let mut path = config_dir();
+    path.push("window_config");
+    path.push(format!("{:x}.json", hasher.finish()));
+    path
+}
+
+/// this app's config directory: `$XDG_CONFIG_HOME/diwata`, falling back
+/// to `$HOME/.config/diwata`, and finally to the current directory if
+/// neither is set
+fn config_dir() -> PathBuf {
+    if let Some(xdg) = env::var_os("XDG_CONFIG_HOME") {
+        if !xdg.is_empty() {
+            return PathBuf::from(xdg).join("diwata");
+        }
+    }
+    if let Some(home) = env::var_os("HOME") {
+        if !home.is_empty() {
+            return PathBuf::from(home).join(".config").join("diwata");
+        }
+    }
+    PathBuf::from(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_table_has_no_override() {
+        let config = WindowConfig::new();
+        assert!(config.get("product").is_none());
+    }
+
+    #[test]
+    fn set_then_get_round_trips() {
+        let mut config = WindowConfig::new();
+        let mut window_override = WindowOverride::default();
+        window_override.hidden_tabs.push("review".to_string());
+        config.set("product", window_override);
+
+        let stored = config.get("product").unwrap();
+        assert_eq!(stored.hidden_tabs, vec!["review".to_string()]);
+    }
+
+    #[test]
+    fn path_for_db_is_stable_for_the_same_url() {
+        let a = path_for_db("postgres://localhost/bazaar_v8");
+        let b = path_for_db("postgres://localhost/bazaar_v8");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn path_for_db_is_anchored_to_the_config_dir_not_the_cwd() {
+        env::set_var("XDG_CONFIG_HOME", "/tmp/diwata-test-config-home");
+        let path = path_for_db("postgres://localhost/bazaar_v8");
+        env::remove_var("XDG_CONFIG_HOME");
+        assert!(path.starts_with("/tmp/diwata-test-config-home/diwata"));
+    }
+
+    #[test]
+    fn grouping_override_round_trips() {
+        let mut config = WindowConfig::new();
+        assert!(config.grouping().is_none());
+
+        config.set_grouping(Some(GroupingOverride::SingleBucket("public".to_string())));
+        match config.grouping() {
+            Some(&GroupingOverride::SingleBucket(ref name)) => assert_eq!(name, "public"),
+            other => panic!("expected SingleBucket override, got {:?}", other),
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use error::ServiceError;
+use intel::Tab;
 use intel::Window;
 use rustorm::EntityManager;
 use rustorm::RecordManager;
 use rustorm::Table;
+use rustorm::TableName;
 
 pub struct Context {
     pub em: EntityManager,
@@ -26,4 +28,14 @@ impl Context {
             windows,
         })
     }
+
+    /// build the full `Tab` for a has_many or indirect relation of
+    /// `window` that was only derived as a lightweight descriptor,
+    /// caching it so opening it again is free
+    pub fn load_tab(&self, window: &Window, table_name: &TableName) -> Result<Tab, ServiceError> {
+        let db_url = &::get_db_url()?;
+        let mut cache_pool = ::cache::CACHE_POOL.lock().unwrap();
+        let tab = cache_pool.get_cached_tab(db_url, window, table_name, &self.tables)?;
+        Ok(tab)
+    }
 }